@@ -1,12 +1,13 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use clipboard::{ClipboardContext, ClipboardProvider};
 use glob::Pattern;
-use gitignore::File as GitignoreFile;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use serde::Deserialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use walkdir::WalkDir;
 
 #[derive(Parser)]
 #[command(author, version, about = "Copy directory contents to clipboard")]
@@ -19,63 +20,374 @@ struct Args {
     #[arg(short, long)]
     recursive: bool,
 
-    /// Filter files by pattern (e.g., "*.rs")
+    /// Filter files by gitignore-style glob (e.g., "*.rs" or "src/**/*.rs").
+    /// May be passed multiple times; a file is included if it matches any.
     #[arg(short, long)]
-    filter: Option<String>,
+    filter: Vec<String>,
+
+    /// Exclude files by gitignore-style glob, applied after --filter. May be
+    /// passed multiple times.
+    #[arg(short, long)]
+    exclude: Vec<String>,
 
     // Use xsel instead of the clipboard crate
     #[arg(short, long, default_value = "false")]
     x11: bool,
 
-    /// Ignore files specified in .gitignore
+    /// Don't respect any ignore files (.gitignore, .ignore, .hgignore, global
+    /// gitignore, .git/info/exclude) or the config file's `ignore` list
     #[arg(long)]
     no_ignore: bool,
+
+    /// Reduce filtering: -u disables .ignore/.gitignore discovery, -uu also
+    /// stops skipping common noise directories (.git, target, node_modules)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    unrestricted: u8,
+
+    /// Override the detected project root used to locate root-level ignore
+    /// files (auto-detected by walking up from base-dir to a .git or .hg)
+    #[arg(long, alias = "project-root")]
+    root: Option<String>,
+
+    /// How to render each directory's listing block [default: long]
+    #[arg(long, value_enum)]
+    listing_format: Option<ListingFormat>,
+
+    /// Path to a dir_to_clipboard.toml config file (defaults to looking for
+    /// one named that in --base-dir)
+    #[arg(long)]
+    config: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ListingFormat {
+    /// `ls -l`-style listing: permissions, size, mtime, name
+    Long,
+    /// Tree-style (`├──`/`└──`) list of the files matched in that directory
+    Tree,
+    /// No directory listing block at all
+    None,
+}
+
+/// Persisted settings for a project, so contributors don't have to retype
+/// long invocations. CLI flags always win over values found here.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct ConfigFile {
+    filter: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    ignore: Option<Vec<String>>,
+    recursive: Option<bool>,
+    listing_format: Option<String>,
+}
+
+/// Loads the file given by `--config`, or `dir_to_clipboard.toml` in
+/// `base_dir` when no `--config` is given. Returns the default (empty) config
+/// when that file does not exist; a missing `--config` file does not fall
+/// back to `base_dir`.
+fn load_config(base_dir: &Path, config_override: Option<&str>) -> Result<ConfigFile> {
+    let path = match config_override {
+        Some(p) => PathBuf::from(p),
+        None => base_dir.join("dir_to_clipboard.toml"),
+    };
+
+    if !path.exists() {
+        return Ok(ConfigFile::default());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))
+}
+
+/// Directories that are skipped by default, regardless of ignore files,
+/// unless the user passes `-uu`.
+const NOISE_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+fn is_noise_dir(entry: &ignore::DirEntry) -> bool {
+    entry.file_type().is_some_and(|t| t.is_dir())
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| NOISE_DIRS.contains(&name))
+}
+
+/// Builds a stable, locale-independent `ls -l`-style listing of `dir_path`'s
+/// immediate entries, without shelling out to `ls`.
+fn get_directory_listing(dir_path: &Path) -> Result<String> {
+    let mut entries: Vec<_> = fs::read_dir(dir_path)
+        .context("Failed to read directory")?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut listing = String::new();
+    for entry in entries {
+        let metadata = entry.metadata().context("Failed to read metadata")?;
+        let permissions = format_permissions(&metadata);
+        let mtime = metadata
+            .modified()
+            .map(format_mtime)
+            .unwrap_or_else(|_| "????-??-?? ??:??".to_string());
+
+        listing.push_str(&format!(
+            "{} {:>10} {} {}\n",
+            permissions,
+            metadata.len(),
+            mtime,
+            entry.file_name().to_string_lossy()
+        ));
+    }
+
+    Ok(listing)
+}
+
+#[cfg(unix)]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    format_mode(metadata.permissions().mode(), metadata.is_dir())
 }
 
-fn get_directory_listing(path: &str) -> Result<String> {
-    let output = Command::new("ls")
-        .arg("-l")
-        .arg(path)
-        .output()
-        .context("Failed to execute ls command")?;
+#[cfg(not(unix))]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    let kind = if metadata.is_dir() { 'd' } else { '-' };
+    if metadata.permissions().readonly() {
+        format!("{}r--r--r--", kind)
+    } else {
+        format!("{}rw-rw-rw-", kind)
+    }
+}
+
+#[cfg(unix)]
+fn format_mode(mode: u32, is_dir: bool) -> String {
+    let bit = |mask: u32, ch: char| if mode & mask != 0 { ch } else { '-' };
+    format!(
+        "{}{}{}{}{}{}{}{}{}{}",
+        if is_dir { 'd' } else { '-' },
+        bit(0o400, 'r'),
+        bit(0o200, 'w'),
+        bit(0o100, 'x'),
+        bit(0o040, 'r'),
+        bit(0o020, 'w'),
+        bit(0o010, 'x'),
+        bit(0o004, 'r'),
+        bit(0o002, 'w'),
+        bit(0o001, 'x'),
+    )
+}
+
+/// Formats a modification time as `YYYY-MM-DD HH:MM` (UTC) without pulling in
+/// a date/time crate.
+fn format_mtime(modified: std::time::SystemTime) -> String {
+    let secs = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let days = (secs / 86_400) as i64;
+    let time_of_day = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        time_of_day / 3600,
+        (time_of_day % 3600) / 60
+    )
+}
 
-    String::from_utf8(output.stdout).context("Failed to parse ls output")
+/// Days-since-epoch to (year, month, day), per Howard Hinnant's
+/// `civil_from_days` algorithm (public domain).
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
+/// Sorted names of the files in `dir_path` (non-recursive) that pass the
+/// active filters, for rendering a `tree`-style listing block.
+fn matching_file_names(dir_path: &Path, opts: &WalkOptions) -> Vec<String> {
+    let mut names: Vec<String> = build_walker(dir_path, false, opts)
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|entry| {
+            entry.depth() > 0
+                && entry.file_type().is_some_and(|t| t.is_file())
+                && should_process_file(entry.path(), opts.base_dir, opts.filters, opts.excludes)
+        })
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect();
+    names.sort();
+    names
+}
+
+fn format_tree_listing(names: &[String]) -> String {
+    let mut tree = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let branch = if i + 1 == names.len() {
+            "└── "
+        } else {
+            "├── "
+        };
+        tree.push_str(branch);
+        tree.push_str(name);
+        tree.push('\n');
+    }
+    tree
 }
 
 fn read_file_contents<P: AsRef<Path>>(path: P) -> Result<String> {
     fs::read_to_string(path).context("Failed to read file")
 }
 
-fn should_process_file(path: &Path, filter_pattern: Option<&Pattern>, gitignore: Option<&GitignoreFile>) -> bool {
-    if let Some(gitignore) = gitignore {
-        if gitignore.is_excluded(path).unwrap_or(false) {
-            return false;
-        }
+/// A single `--filter`/`--exclude` glob, compiled once and matched
+/// gitignore-style: a pattern with no slash matches against any path
+/// component (like a bare `.gitignore` entry), while a pattern containing a
+/// slash is anchored and matched against the whole path relative to
+/// `base_dir`. A leading `/` is just an explicit (redundant) anchor.
+struct GlobPattern {
+    pattern: Pattern,
+    anchored: bool,
+}
+
+impl GlobPattern {
+    fn new(raw: &str) -> Result<Self> {
+        let anchored = raw.contains('/');
+        let trimmed = raw.strip_prefix('/').unwrap_or(raw);
+        let pattern = Pattern::new(trimmed).context("Invalid glob pattern")?;
+        Ok(GlobPattern { pattern, anchored })
     }
 
-    if let Some(pattern) = filter_pattern {
-        if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-            pattern.matches(file_name)
+    fn matches(&self, rel_path: &Path) -> bool {
+        if self.anchored {
+            // Like gitignore, `*` and `?` stop at `/`; only `**` spans directories.
+            let options = glob::MatchOptions {
+                require_literal_separator: true,
+                ..glob::MatchOptions::new()
+            };
+            self.pattern.matches_path_with(rel_path, options)
         } else {
-            false
+            rel_path
+                .components()
+                .any(|c| c.as_os_str().to_str().is_some_and(|s| self.pattern.matches(s)))
         }
-    } else {
-        true
     }
 }
 
-fn directory_has_matching_files(
-    dir_path: &Path,
-    filter_pattern: Option<&Pattern>,
-    gitignore: Option<&GitignoreFile>,
+fn should_process_file(
+    path: &Path,
+    base_dir: &Path,
+    filters: &[GlobPattern],
+    excludes: &[GlobPattern],
 ) -> bool {
-    WalkDir::new(dir_path)
-        .min_depth(1)
-        .into_iter()
+    let rel_path = path.strip_prefix(base_dir).unwrap_or(path);
+
+    if excludes.iter().any(|glob| glob.matches(rel_path)) {
+        return false;
+    }
+
+    filters.is_empty() || filters.iter().any(|glob| glob.matches(rel_path))
+}
+
+/// Walks upward from `base_dir` looking for the project's origin, i.e. the
+/// directory containing a `.git` or `.hg`, so that ignore files sitting at
+/// the repo root still apply when the tool is invoked from a subfolder.
+/// `root_override` (`--root`/`--project-root`) always wins over detection.
+fn discover_project_root(base_dir: &Path, root_override: Option<&str>) -> Option<PathBuf> {
+    if let Some(root) = root_override {
+        return Some(PathBuf::from(root));
+    }
+
+    let mut dir = base_dir.canonicalize().ok()?;
+    loop {
+        if dir.join(".git").exists() || dir.join(".hg").exists() {
+            return Some(dir);
+        }
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
+
+/// Bundles the filtering/ignore settings threaded through every walk, so
+/// helpers that build or probe a walker don't need a growing positional
+/// parameter list.
+struct WalkOptions<'a> {
+    base_dir: &'a Path,
+    filters: &'a [GlobPattern],
+    excludes: &'a [GlobPattern],
+    no_ignore: bool,
+    unrestricted: u8,
+    root_override: Option<&'a str>,
+    extra_ignore: Option<&'a Gitignore>,
+}
+
+fn build_walker(dir_path: &Path, recursive: bool, opts: &WalkOptions) -> WalkBuilder {
+    let ignore_disabled = opts.no_ignore || opts.unrestricted >= 1;
+
+    let mut builder = WalkBuilder::new(dir_path);
+    builder
+        .hidden(false)
+        .ignore(!ignore_disabled)
+        .git_ignore(!ignore_disabled)
+        .git_global(!ignore_disabled)
+        .git_exclude(!ignore_disabled)
+        .max_depth(if recursive { None } else { Some(1) });
+
+    if !ignore_disabled {
+        // Mercurial has no built-in support in the `ignore` crate; treat
+        // .hgignore like a per-directory custom ignore file instead.
+        builder.add_custom_ignore_filename(".hgignore");
+
+        if let Some(root) = discover_project_root(dir_path, opts.root_override) {
+            for name in [".gitignore", ".hgignore", ".ignore"] {
+                let _ = builder.add_ignore(root.join(name));
+            }
+            let _ = builder.add_ignore(root.join(".git").join("info").join("exclude"));
+        }
+    }
+
+    let skip_noise_dirs = opts.unrestricted < 2;
+    let extra_ignore = if ignore_disabled {
+        None
+    } else {
+        opts.extra_ignore.cloned()
+    };
+    if skip_noise_dirs || extra_ignore.is_some() {
+        builder.filter_entry(move |entry| {
+            if skip_noise_dirs && is_noise_dir(entry) {
+                return false;
+            }
+            if let Some(gi) = &extra_ignore {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                if gi.matched(entry.path(), is_dir).is_ignore() {
+                    return false;
+                }
+            }
+            true
+        });
+    }
+
+    builder
+}
+
+fn directory_has_matching_files(dir_path: &Path, opts: &WalkOptions) -> bool {
+    build_walker(dir_path, true, opts)
+        .build()
         .filter_map(|e| e.ok())
         .any(|entry| {
-            entry.file_type().is_file()
-                && should_process_file(entry.path(), filter_pattern, gitignore)
+            entry.file_type().is_some_and(|t| t.is_file())
+                && should_process_file(entry.path(), opts.base_dir, opts.filters, opts.excludes)
         })
 }
 
@@ -99,52 +411,101 @@ fn copy_to_clipboard(contents: &str) -> Result<()> {
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let base_dir = Path::new(&args.base_dir);
 
-    // Convert filter pattern if provided
-    let filter_pattern = args
-        .filter
-        .as_ref()
-        .map(|f| Pattern::new(f))
-        .transpose()
-        .context("Invalid filter pattern")?;
+    let config = load_config(base_dir, args.config.as_deref())?;
 
-    let mut ctx: ClipboardContext = ClipboardProvider::new()
-        .map_err(|e| anyhow::anyhow!("Failed to initialize clipboard: {}", e))?;
+    // CLI flags always win over the config file.
+    let raw_filters = if !args.filter.is_empty() {
+        args.filter.clone()
+    } else {
+        config.filter.clone().unwrap_or_default()
+    };
+    let raw_excludes = if !args.exclude.is_empty() {
+        args.exclude.clone()
+    } else {
+        config.exclude.clone().unwrap_or_default()
+    };
+    let recursive = args.recursive || config.recursive.unwrap_or(false);
+    let listing_format = args.listing_format.unwrap_or_else(|| {
+        config
+            .listing_format
+            .as_deref()
+            .and_then(|f| ListingFormat::from_str(f, true).ok())
+            .unwrap_or(ListingFormat::Long)
+    });
 
-    let gitignore_path = Path::new(&args.base_dir).join(Path::new(".gitignore"));
-    let gitignore = if args.no_ignore {
+    let filters = raw_filters
+        .iter()
+        .map(|f| GlobPattern::new(f))
+        .collect::<Result<Vec<_>>>()?;
+    let excludes = raw_excludes
+        .iter()
+        .map(|e| GlobPattern::new(e))
+        .collect::<Result<Vec<_>>>()?;
+
+    let extra_ignore_patterns = config.ignore.clone().unwrap_or_default();
+    let extra_ignore = if extra_ignore_patterns.is_empty() {
         None
     } else {
-        GitignoreFile::new(gitignore_path.as_path()).ok() // Ignore errors
+        let mut builder = GitignoreBuilder::new(base_dir);
+        for pattern in &extra_ignore_patterns {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("Invalid ignore pattern in config: {}", pattern))?;
+        }
+        Some(builder.build().context("Failed to build ignore patterns from config")?)
+    };
+
+    let opts = WalkOptions {
+        base_dir,
+        filters: &filters,
+        excludes: &excludes,
+        no_ignore: args.no_ignore,
+        unrestricted: args.unrestricted,
+        root_override: args.root.as_deref(),
+        extra_ignore: extra_ignore.as_ref(),
     };
 
+    let mut ctx: ClipboardContext = ClipboardProvider::new()
+        .map_err(|e| anyhow::anyhow!("Failed to initialize clipboard: {}", e))?;
+
     // Start building the output string
     let mut output = String::new();
 
-    let mut walker = WalkDir::new(&args.base_dir).min_depth(1);
-
-    if !args.recursive {
-        walker = walker.max_depth(1);
-    }
+    let walker = build_walker(base_dir, recursive, &opts).build();
 
     let mut current_dir: Option<String> = None;
 
-    for entry in walker.into_iter().filter_map(|e| e.ok()) {
+    for entry in walker.filter_map(|e| e.ok()) {
+        if entry.depth() == 0 {
+            continue;
+        }
+
         let path = entry.path();
 
-        if entry.file_type().is_file()
-            && should_process_file(path, filter_pattern.as_ref(), gitignore.as_ref())
+        if entry.file_type().is_some_and(|t| t.is_file())
+            && should_process_file(path, base_dir, &filters, &excludes)
         {
             // If we're in a new directory that contains matching files, add its listing
             let dir_path = path.parent().unwrap().to_string_lossy().to_string();
             if current_dir.as_ref() != Some(&dir_path) {
                 // For recursive mode, check if directory has matching files
-                if !args.recursive
-                    || directory_has_matching_files(Path::new(&dir_path), filter_pattern.as_ref(), gitignore.as_ref())
-                {
-                    output.push_str(&format!("\n=== Directory: {} ===\n", dir_path));
-                    if let Ok(listing) = get_directory_listing(&dir_path) {
-                        output.push_str(&listing);
+                if !recursive || directory_has_matching_files(Path::new(&dir_path), &opts) {
+                    if listing_format != ListingFormat::None {
+                        output.push_str(&format!("\n=== Directory: {} ===\n", dir_path));
+                        match listing_format {
+                            ListingFormat::Long => {
+                                if let Ok(listing) = get_directory_listing(Path::new(&dir_path)) {
+                                    output.push_str(&listing);
+                                }
+                            }
+                            ListingFormat::Tree => {
+                                let names = matching_file_names(Path::new(&dir_path), &opts);
+                                output.push_str(&format_tree_listing(&names));
+                            }
+                            ListingFormat::None => unreachable!(),
+                        }
                     }
                     current_dir = Some(dir_path);
                 }
@@ -170,12 +531,65 @@ fn main() -> Result<()> {
     println!("Directory contents and file contents have been copied to clipboard!");
 
     // Print summary of what was processed
-    if let Some(pattern) = &args.filter {
-        println!("Filtered files using pattern: {}", pattern);
+    if !raw_filters.is_empty() {
+        println!("Filtered files using patterns: {}", raw_filters.join(", "));
     }
-    if args.recursive {
+    if !raw_excludes.is_empty() {
+        println!("Excluded files using patterns: {}", raw_excludes.join(", "));
+    }
+    if recursive {
         println!("Processed subdirectories recursively (showing only directories with matching files)");
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn civil_from_days_epoch() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+    }
+
+    #[test]
+    fn civil_from_days_known_date() {
+        // 19_675 days since epoch is 2023-11-14.
+        assert_eq!(civil_from_days(19_675), (2023, 11, 14));
+    }
+
+    #[test]
+    fn format_mtime_epoch() {
+        assert_eq!(format_mtime(std::time::UNIX_EPOCH), "1970-01-01 00:00");
+    }
+
+    #[test]
+    fn format_mtime_known_timestamp() {
+        let modified = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(format_mtime(modified), "2023-11-14 22:13");
+    }
+
+    #[test]
+    fn glob_pattern_unanchored_matches_any_component() {
+        let pattern = GlobPattern::new("*.rs").unwrap();
+        assert!(pattern.matches(Path::new("src/main.rs")));
+        assert!(pattern.matches(Path::new("main.rs")));
+        assert!(!pattern.matches(Path::new("src/main.txt")));
+    }
+
+    #[test]
+    fn glob_pattern_anchored_matches_full_relative_path() {
+        let pattern = GlobPattern::new("src/**/*.rs").unwrap();
+        assert!(pattern.matches(Path::new("src/nested/mod.rs")));
+        assert!(!pattern.matches(Path::new("other/nested/mod.rs")));
+    }
+
+    #[test]
+    fn glob_pattern_leading_slash_is_an_explicit_anchor() {
+        let pattern = GlobPattern::new("/Cargo.toml").unwrap();
+        assert!(pattern.matches(Path::new("Cargo.toml")));
+        assert!(!pattern.matches(Path::new("nested/Cargo.toml")));
+    }
+}